@@ -1,15 +1,28 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::{collections::HashMap, ffi::c_void, fmt::Display, ops::Neg};
+use std::{
+    borrow::Cow, cmp::Ordering, collections::HashMap, ffi::c_void, fmt::Display, ops::Neg, rc::Rc,
+};
 
+// `BigInt`'s `Serialize`/`Deserialize` impls (derived on `ValueData` below) only
+// exist when num-bigint's `serde` feature is enabled; this crate's `Cargo.toml`
+// must depend on `num-bigint` with `features = ["serde"]`.
 use num_bigint::BigInt;
 use qir_backend::Pauli;
 use qsc_frontend::resolve::DefId;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+/// A Q# runtime value.
+///
+/// `Array`, `Tuple`, and `String` are backed by [`Rc`] so that cloning a
+/// `Value` (which the interpreter does constantly) is a refcount bump rather
+/// than a deep copy; mutation sites should use `Rc::make_mut` to get
+/// copy-on-write semantics, cloning the underlying data only when it is
+/// actually shared.
 #[derive(Clone, Debug)]
 pub enum Value {
-    Array(Vec<Value>),
+    Array(Rc<Vec<Value>>),
     BigInt(BigInt),
     Bool(bool),
     Closure(DefId, HashMap<DefId, Value>),
@@ -20,9 +33,128 @@ pub enum Value {
     Qubit(*mut c_void),
     Range(Option<i64>, Option<i64>, Option<i64>),
     Result(bool),
+    String(Rc<str>),
+    Tuple(Rc<Vec<Value>>),
+    /// An instance of a user-defined type: the type's id and name, plus its named
+    /// fields in declaration order.
+    Udt(DefId, Rc<str>, Rc<Vec<(Rc<str>, Value)>>),
+}
+
+/// The wire format used to (de)serialize a [`Value`].
+///
+/// `Qubit`, `Closure`, and `Global` carry data that cannot be meaningfully
+/// serialized: a raw pointer, or identifiers that are only valid within a
+/// particular running interpreter. Each is represented by an opaque
+/// placeholder that can be serialized (to aid debugging/logging) but that
+/// always fails to deserialize back into a [`Value`].
+#[derive(Serialize, Deserialize)]
+enum ValueData {
+    Array(Vec<Value>),
+    BigInt(BigInt),
+    Bool(bool),
+    Closure(String),
+    Double(f64),
+    Global(String),
+    Int(i64),
+    Pauli(PauliData),
+    Qubit(usize),
+    Range(Option<i64>, Option<i64>, Option<i64>),
+    Result(bool),
     String(String),
     Tuple(Vec<Value>),
-    Udt,
+    Udt {
+        id: String,
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+enum PauliData {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl From<Pauli> for PauliData {
+    fn from(value: Pauli) -> Self {
+        match value {
+            Pauli::I => PauliData::I,
+            Pauli::X => PauliData::X,
+            Pauli::Y => PauliData::Y,
+            Pauli::Z => PauliData::Z,
+        }
+    }
+}
+
+impl From<PauliData> for Pauli {
+    fn from(value: PauliData) -> Self {
+        match value {
+            PauliData::I => Pauli::I,
+            PauliData::X => Pauli::X,
+            PauliData::Y => Pauli::Y,
+            PauliData::Z => Pauli::Z,
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Array(v) => ValueData::Array((**v).clone()),
+            Value::BigInt(v) => ValueData::BigInt(v.clone()),
+            Value::Bool(v) => ValueData::Bool(*v),
+            Value::Closure(id, _) => ValueData::Closure(format!("{id:?}")),
+            Value::Double(v) => ValueData::Double(*v),
+            Value::Global(id) => ValueData::Global(format!("{id:?}")),
+            Value::Int(v) => ValueData::Int(*v),
+            Value::Pauli(v) => ValueData::Pauli((*v).into()),
+            Value::Qubit(v) => ValueData::Qubit(*v as usize),
+            Value::Range(start, step, end) => ValueData::Range(*start, *step, *end),
+            Value::Result(v) => ValueData::Result(*v),
+            Value::String(v) => ValueData::String(v.to_string()),
+            Value::Tuple(v) => ValueData::Tuple((**v).clone()),
+            Value::Udt(id, name, fields) => ValueData::Udt {
+                id: format!("{id:?}"),
+                name: name.to_string(),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| (field.to_string(), value.clone()))
+                    .collect(),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match ValueData::deserialize(deserializer)? {
+            ValueData::Array(v) => Ok(Value::Array(Rc::new(v))),
+            ValueData::BigInt(v) => Ok(Value::BigInt(v)),
+            ValueData::Bool(v) => Ok(Value::Bool(v)),
+            ValueData::Closure(_) => Err(de::Error::custom(
+                "cannot deserialize a Closure value: closures are only meaningful within the interpreter that created them",
+            )),
+            ValueData::Double(v) => Ok(Value::Double(v)),
+            ValueData::Global(_) => Err(de::Error::custom(
+                "cannot deserialize a Global value: globals are only meaningful within the interpreter that created them",
+            )),
+            ValueData::Int(v) => Ok(Value::Int(v)),
+            ValueData::Pauli(v) => Ok(Value::Pauli(v.into())),
+            ValueData::Qubit(_) => Err(de::Error::custom(
+                "cannot deserialize a Qubit value: qubits are only meaningful within a running simulation",
+            )),
+            ValueData::Range(start, step, end) => Ok(Value::Range(start, step, end)),
+            ValueData::Result(v) => Ok(Value::Result(v)),
+            ValueData::String(v) => Ok(Value::String(v.into())),
+            ValueData::Tuple(v) => Ok(Value::Tuple(Rc::new(v))),
+            ValueData::Udt { .. } => Err(de::Error::custom(
+                "cannot deserialize a Udt value: user-defined type ids are only meaningful within the compilation that produced them",
+            )),
+        }
+    }
 }
 
 impl Display for Value {
@@ -30,12 +162,12 @@ impl Display for Value {
         match self {
             Value::Array(arr) => {
                 write!(f, "[")?;
-                join(f, arr.iter(), ", ")?;
+                join(f, arr.iter(), ", ", |f, v| v.fmt(f))?;
                 write!(f, "]")
             }
             Value::BigInt(v) => write!(f, "{v}"),
             Value::Bool(v) => write!(f, "{v}"),
-            Value::Closure(_, _) => todo!(),
+            Value::Closure(_, _) => write!(f, "<closure>"),
             Value::Double(v) => {
                 if (v.floor() - v.ceil()).abs() < f64::EPSILON {
                     // The value is a whole number, which by convention is displayed with one decimal point
@@ -45,7 +177,7 @@ impl Display for Value {
                     write!(f, "{v}")
                 }
             }
-            Value::Global(_) => todo!(),
+            Value::Global(_) => write!(f, "<callable>"),
             Value::Int(v) => write!(f, "{v}"),
             Value::Pauli(v) => match v {
                 Pauli::I => write!(f, "PauliI"),
@@ -74,17 +206,154 @@ impl Display for Value {
             Value::String(v) => write!(f, "{v}"),
             Value::Tuple(tup) => {
                 write!(f, "(")?;
-                join(f, tup.iter(), ", ")?;
+                join(f, tup.iter(), ", ", |f, v| v.fmt(f))?;
+                write!(f, ")")
+            }
+            Value::Udt(_, name, fields) => {
+                write!(f, "{name}(")?;
+                join(f, fields.iter(), ", ", |f, (field, value)| {
+                    write!(f, "{field} = {value}")
+                })?;
                 write!(f, ")")
             }
-            Value::Udt => todo!(),
         }
     }
 }
 
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Closure(a, _), Value::Closure(b, _)) => a == b,
+            (Value::Double(a), Value::Double(b)) => float_eq(*a, *b),
+            (Value::Global(a), Value::Global(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Pauli(a), Value::Pauli(b)) => a == b,
+            (Value::Qubit(a), Value::Qubit(b)) => a == b,
+            (Value::Range(s1, t1, e1), Value::Range(s2, t2, e2)) => {
+                s1 == s2 && t1 == t2 && e1 == e2
+            }
+            (Value::Result(a), Value::Result(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Tuple(a), Value::Tuple(b)) => a == b,
+            (Value::Udt(id1, _, f1), Value::Udt(id2, _, f2)) => id1 == id2 && f1 == f2,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Array(a), Value::Array(b)) => a.partial_cmp(b),
+            (Value::BigInt(a), Value::BigInt(b)) => a.partial_cmp(b),
+            (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+            (Value::Double(a), Value::Double(b)) => Some(float_cmp(*a, *b)),
+            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+            (Value::Pauli(a), Value::Pauli(b)) => pauli_rank(*a).partial_cmp(&pauli_rank(*b)),
+            (Value::Range(s1, t1, e1), Value::Range(s2, t2, e2)) => {
+                (s1, t1, e1).partial_cmp(&(s2, t2, e2))
+            }
+            (Value::Result(a), Value::Result(b)) => a.partial_cmp(b),
+            (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+            (Value::Tuple(a), Value::Tuple(b)) => a.partial_cmp(b),
+            (Value::Udt(id1, _, f1), Value::Udt(id2, _, f2)) if id1 == id2 => f1.partial_cmp(f2),
+            // `Qubit`, `Closure`, and `Global` have no natural order, and values of
+            // mismatched variants (including `Udt`s of different types) are not
+            // comparable at all: callers (e.g. evaluating `<`/`>`) should treat
+            // `None` here as a type error rather than fall back to some order.
+            _ => None,
+        }
+    }
+}
+
+/// Compares two `Double`s, treating NaN as equal to itself (and greater than every
+/// other value) so that `Value`'s `Eq`/`PartialOrd` impls and [`Value::total_cmp`] are
+/// well-defined even for NaN.
+fn float_cmp(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or_else(|| match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => Ordering::Equal,
+    })
+}
+
+fn float_eq(a: f64, b: f64) -> bool {
+    float_cmp(a, b) == Ordering::Equal
+}
+
+/// An arbitrary but stable rank for each `Pauli` value, used only to give `Value`
+/// a total order; Q# does not define a natural order for `Pauli`.
+fn pauli_rank(pauli: Pauli) -> u8 {
+    match pauli {
+        Pauli::I => 0,
+        Pauli::X => 1,
+        Pauli::Y => 2,
+        Pauli::Z => 3,
+    }
+}
+
+/// The declaration order of a `Value`'s variant, used as a deterministic (if
+/// semantically meaningless) tie-breaker so that [`Value::total_cmp`] is total
+/// across variants.
+fn variant_rank(value: &Value) -> u8 {
+    match value {
+        Value::Array(_) => 0,
+        Value::BigInt(_) => 1,
+        Value::Bool(_) => 2,
+        Value::Closure(_, _) => 3,
+        Value::Double(_) => 4,
+        Value::Global(_) => 5,
+        Value::Int(_) => 6,
+        Value::Pauli(_) => 7,
+        Value::Qubit(_) => 8,
+        Value::Range(_, _, _) => 9,
+        Value::Result(_) => 10,
+        Value::String(_) => 11,
+        Value::Tuple(_) => 12,
+        Value::Udt(_, _, _) => 13,
+    }
+}
+
+/// Lexicographically compares two slices using `cmp` on corresponding elements,
+/// without requiring `T: Ord` (used by [`Value::total_cmp`] to recurse into
+/// `Array`/`Tuple`/`Udt` contents, since `Value` intentionally has no `Ord` impl).
+fn compare_by<T>(a: &[T], b: &[T], mut cmp: impl FnMut(&T, &T) -> Ordering) -> Ordering {
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = cmp(x, y);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// The type id, type name, and named fields of a [`Value::Udt`], as returned by
+/// [`Value::try_into_udt`].
+pub type UdtParts = (DefId, Rc<str>, Rc<Vec<(Rc<str>, Value)>>);
+
 pub struct ConversionError {
-    pub expected: &'static str,
-    pub actual: &'static str,
+    pub expected: Cow<'static, str>,
+    pub actual: Cow<'static, str>,
+}
+
+/// An error from a binary arithmetic, bitwise, or logical operation on [`Value`]s.
+pub enum ArithmeticError {
+    /// The operand types are not supported by the operator.
+    Conversion(ConversionError),
+    /// The operation would divide, or take the remainder, by zero.
+    DivideByZero,
+}
+
+impl From<ConversionError> for ArithmeticError {
+    fn from(error: ConversionError) -> Self {
+        ArithmeticError::Conversion(error)
+    }
 }
 
 impl TryFrom<Value> for i64 {
@@ -95,7 +364,7 @@ impl TryFrom<Value> for i64 {
             Ok(v)
         } else {
             Err(ConversionError {
-                expected: "Int",
+                expected: Cow::Borrowed("Int"),
                 actual: value.type_name(),
             })
         }
@@ -110,7 +379,7 @@ impl TryFrom<Value> for bool {
             Ok(v)
         } else {
             Err(ConversionError {
-                expected: "Bool",
+                expected: Cow::Borrowed("Bool"),
                 actual: value.type_name(),
             })
         }
@@ -122,10 +391,10 @@ impl TryFrom<Value> for String {
 
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         if let Value::String(v) = value {
-            Ok(v)
+            Ok(v.to_string())
         } else {
             Err(ConversionError {
-                expected: "String",
+                expected: Cow::Borrowed("String"),
                 actual: value.type_name(),
             })
         }
@@ -138,10 +407,10 @@ impl Value {
     /// This will return an error if the [Value] is not a [`Value::Array`].
     pub fn try_into_array(self) -> Result<Vec<Self>, ConversionError> {
         if let Value::Array(v) = self {
-            Ok(v)
+            Ok(Rc::try_unwrap(v).unwrap_or_else(|v| (*v).clone()))
         } else {
             Err(ConversionError {
-                expected: "Array",
+                expected: Cow::Borrowed("Array"),
                 actual: self.type_name(),
             })
         }
@@ -152,32 +421,94 @@ impl Value {
     /// This will return an error if the [Value] is not a [`Value::Tuple`].
     pub fn try_into_tuple(self) -> Result<Vec<Self>, ConversionError> {
         if let Value::Tuple(v) = self {
-            Ok(v)
+            Ok(Rc::try_unwrap(v).unwrap_or_else(|v| (*v).clone()))
         } else {
             Err(ConversionError {
-                expected: "Tuple",
+                expected: Cow::Borrowed("Tuple"),
                 actual: self.type_name(),
             })
         }
     }
 
+    /// Creates a new [`Value::Udt`] with the given type id, type name, and named
+    /// fields in declaration order.
     #[must_use]
-    pub fn type_name(&self) -> &'static str {
+    pub fn new_udt(id: DefId, name: Rc<str>, fields: Vec<(Rc<str>, Value)>) -> Self {
+        Value::Udt(id, name, Rc::new(fields))
+    }
+
+    /// Returns the value of the named field on this [`Value::Udt`], or `None` if
+    /// this is not a `Udt` or it has no field with that name.
+    #[must_use]
+    pub fn udt_field(&self, field: &str) -> Option<&Value> {
+        if let Value::Udt(_, _, fields) = self {
+            fields
+                .iter()
+                .find(|(name, _)| &**name == field)
+                .map(|(_, value)| value)
+        } else {
+            None
+        }
+    }
+
+    /// Convert the [Value] into a user-defined-type id, name, and named fields
+    /// # Errors
+    /// This will return an error if the [Value] is not a [`Value::Udt`].
+    pub fn try_into_udt(self) -> Result<UdtParts, ConversionError> {
+        if let Value::Udt(id, name, fields) = self {
+            Ok((id, name, fields))
+        } else {
+            Err(ConversionError {
+                expected: Cow::Borrowed("Udt"),
+                actual: self.type_name(),
+            })
+        }
+    }
+
+    /// Returns a total order over all `Value`s, for sort/Set-like builtins that
+    /// need a definite order even across pairs `partial_cmp` leaves undefined
+    /// (`Qubit`/`Closure`/`Global` identity, mismatched variants): those compare
+    /// by pointer/`DefId`, or by a fixed, semantically arbitrary variant order,
+    /// respectively. Where `partial_cmp` does return `Some`, `total_cmp` agrees
+    /// with it.
+    #[must_use]
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Array(a), Value::Array(b)) => {
+                compare_by(a, b, |x, y| x.total_cmp(y))
+            }
+            (Value::Closure(a, _), Value::Closure(b, _)) => a.cmp(b),
+            (Value::Global(a), Value::Global(b)) => a.cmp(b),
+            (Value::Qubit(a), Value::Qubit(b)) => (*a as usize).cmp(&(*b as usize)),
+            (Value::Tuple(a), Value::Tuple(b)) => compare_by(a, b, |x, y| x.total_cmp(y)),
+            (Value::Udt(id1, _, f1), Value::Udt(id2, _, f2)) => id1.cmp(id2).then_with(|| {
+                compare_by(f1, f2, |(n1, v1), (n2, v2)| n1.cmp(n2).then_with(|| v1.total_cmp(v2)))
+            }),
+            _ => self
+                .partial_cmp(other)
+                .unwrap_or_else(|| variant_rank(self).cmp(&variant_rank(other))),
+        }
+    }
+
+    /// Returns the name of this [`Value`]'s type: the concrete user-defined type
+    /// name for [`Value::Udt`], or a fixed built-in type name otherwise.
+    #[must_use]
+    pub fn type_name(&self) -> Cow<'static, str> {
         match self {
-            Value::Array(_) => "Array",
-            Value::BigInt(_) => "BigInt",
-            Value::Bool(_) => "Bool",
-            Value::Closure(_, _) => "Closure",
-            Value::Double(_) => "Double",
-            Value::Global(_) => "Global",
-            Value::Int(_) => "Int",
-            Value::Pauli(_) => "Pauli",
-            Value::Qubit(_) => "Qubit",
-            Value::Range(_, _, _) => "Range",
-            Value::Result(_) => "Result",
-            Value::String(_) => "String",
-            Value::Tuple(_) => "Tuple",
-            Value::Udt => "Udt",
+            Value::Array(_) => Cow::Borrowed("Array"),
+            Value::BigInt(_) => Cow::Borrowed("BigInt"),
+            Value::Bool(_) => Cow::Borrowed("Bool"),
+            Value::Closure(_, _) => Cow::Borrowed("Closure"),
+            Value::Double(_) => Cow::Borrowed("Double"),
+            Value::Global(_) => Cow::Borrowed("Global"),
+            Value::Int(_) => Cow::Borrowed("Int"),
+            Value::Pauli(_) => Cow::Borrowed("Pauli"),
+            Value::Qubit(_) => Cow::Borrowed("Qubit"),
+            Value::Range(_, _, _) => Cow::Borrowed("Range"),
+            Value::Result(_) => Cow::Borrowed("Result"),
+            Value::String(_) => Cow::Borrowed("String"),
+            Value::Tuple(_) => Cow::Borrowed("Tuple"),
+            Value::Udt(_, name, _) => Cow::Owned(name.to_string()),
         }
     }
 
@@ -192,24 +523,596 @@ impl Value {
             Value::Double(v) => Ok(Value::Double(v.neg())),
             Value::Int(v) => Ok(Value::Int(v.wrapping_neg())),
             _ => Err(ConversionError {
-                expected: "Int, BigInt, or Double",
+                expected: Cow::Borrowed("Int, BigInt, or Double"),
                 actual: self.type_name(),
             }),
         }
     }
+
+    /// Returns the sum of this [`Value`] and `other`, or the concatenation of the two
+    /// if they are `String`s or `Array`s.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int`, `BigInt`,
+    /// `Double`, `String`, or `Array`.
+    pub fn arithmetic_add(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_add(b))),
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a + b)),
+            (Value::Double(a), Value::Double(b)) => Ok(Value::Double(a + b)),
+            (Value::String(a), Value::String(b)) => {
+                Ok(Value::String(Rc::from(format!("{a}{b}"))))
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                Ok(Value::Array(Rc::new(a.iter().chain(b.iter()).cloned().collect())))
+            }
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the difference between this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int`, `BigInt`,
+    /// or `Double`.
+    pub fn arithmetic_subtract(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_sub(b))),
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a - b)),
+            (Value::Double(a), Value::Double(b)) => Ok(Value::Double(a - b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the product of this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int`, `BigInt`,
+    /// or `Double`.
+    pub fn arithmetic_multiply(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_mul(b))),
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a * b)),
+            (Value::Double(a), Value::Double(b)) => Ok(Value::Double(a * b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the quotient of this [`Value`] divided by `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int`, `BigInt`,
+    /// or `Double`, or [`ArithmeticError::DivideByZero`] if `other` is zero and the values
+    /// are `Int` or `BigInt`.
+    pub fn arithmetic_divide(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(_), Value::Int(0)) => Err(ArithmeticError::DivideByZero),
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_div(b))),
+            (Value::BigInt(_), Value::BigInt(b)) if b == BigInt::from(0) => {
+                Err(ArithmeticError::DivideByZero)
+            }
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a / b)),
+            (Value::Double(a), Value::Double(b)) => Ok(Value::Double(a / b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the remainder of this [`Value`] divided by `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int` or `BigInt`,
+    /// or [`ArithmeticError::DivideByZero`] if `other` is zero.
+    pub fn arithmetic_modulo(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(_), Value::Int(0)) => Err(ArithmeticError::DivideByZero),
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_rem(b))),
+            (Value::BigInt(_), Value::BigInt(b)) if b == BigInt::from(0) => {
+                Err(ArithmeticError::DivideByZero)
+            }
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a % b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Raises this [`Value`] to the power of `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the types are not one of `Int` raised to an
+    /// `Int`, `BigInt` raised to an `Int`, or `Double` raised to a `Double`, or if an `Int`
+    /// exponent is negative.
+    pub fn arithmetic_exponent(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(base), Value::Int(exp)) => Ok(Value::Int(
+                base.wrapping_pow(non_negative_exponent(exp)?),
+            )),
+            (Value::BigInt(base), Value::Int(exp)) => {
+                Ok(Value::BigInt(base.pow(non_negative_exponent(exp)?)))
+            }
+            (Value::Double(base), Value::Double(exp)) => Ok(Value::Double(base.powf(exp))),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the bitwise AND of this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int` or `BigInt`.
+    pub fn bitwise_and(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a & b)),
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a & b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the bitwise OR of this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int` or `BigInt`.
+    pub fn bitwise_or(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a | b)),
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a | b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the bitwise XOR of this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Int` or `BigInt`.
+    pub fn bitwise_xor(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a ^ b)),
+            (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::BigInt(a ^ b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns this [`Value`] shifted left by `other` bits.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `self` is not `Int` or `BigInt`, if `other`
+    /// is not `Int`, or if the shift amount is negative.
+    pub fn shift_left(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => {
+                Ok(Value::Int(a.wrapping_shl(non_negative_exponent(b)?)))
+            }
+            (Value::BigInt(a), Value::Int(b)) => {
+                Ok(Value::BigInt(a << non_negative_exponent(b)?))
+            }
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns this [`Value`] shifted right by `other` bits.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `self` is not `Int` or `BigInt`, if `other`
+    /// is not `Int`, or if the shift amount is negative.
+    pub fn shift_right(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => {
+                Ok(Value::Int(a.wrapping_shr(non_negative_exponent(b)?)))
+            }
+            (Value::BigInt(a), Value::Int(b)) => {
+                Ok(Value::BigInt(a >> non_negative_exponent(b)?))
+            }
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the logical AND of this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Bool`.
+    pub fn logical_and(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a && b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+
+    /// Returns the logical OR of this [`Value`] and `other`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the two values are not both `Bool`.
+    pub fn logical_or(self, other: Self) -> Result<Self, ArithmeticError> {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(a || b)),
+            (lhs, rhs) => Err(mismatched_types(&lhs, &rhs)),
+        }
+    }
+}
+
+/// Builds the [`ArithmeticError`] for a pair of values whose types are not a valid
+/// combination for the operator being applied.
+fn mismatched_types(lhs: &Value, rhs: &Value) -> ArithmeticError {
+    ArithmeticError::Conversion(ConversionError {
+        expected: lhs.type_name(),
+        actual: rhs.type_name(),
+    })
+}
+
+/// Converts an `Int` exponent or shift amount into a `u32`, used by the wrapping
+/// exponentiation and shift operators on [`Value`].
+///
+/// # Errors
+///
+/// This function will return an error if `exp` is negative or does not fit in a `u32`.
+fn non_negative_exponent(exp: i64) -> Result<u32, ArithmeticError> {
+    u32::try_from(exp).map_err(|_| {
+        ArithmeticError::Conversion(ConversionError {
+            expected: Cow::Borrowed("non-negative Int"),
+            actual: Cow::Borrowed("negative or out-of-range Int"),
+        })
+    })
 }
 
-fn join<'a>(
+fn join<T>(
     f: &mut std::fmt::Formatter<'_>,
-    mut vals: impl Iterator<Item = &'a Value>,
+    mut vals: impl Iterator<Item = T>,
     sep: &str,
+    mut write_val: impl FnMut(&mut std::fmt::Formatter<'_>, T) -> std::fmt::Result,
 ) -> std::fmt::Result {
     if let Some(v) = vals.next() {
-        v.fmt(f)?;
+        write_val(f, v)?;
     }
     for v in vals {
         write!(f, "{sep}")?;
-        v.fmt(f)?;
+        write_val(f, v)?;
     }
     Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(value: &Value) -> Value {
+        let json = serde_json::to_string(value).expect("value should serialize");
+        serde_json::from_str(&json).expect("value should deserialize")
+    }
+
+    #[test]
+    fn array_round_trips() {
+        let value = Value::Array(Rc::new(vec![Value::Int(1), Value::Int(2)]));
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn big_int_round_trips() {
+        let value = Value::BigInt(BigInt::from(123_456));
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn bool_round_trips() {
+        let value = Value::Bool(true);
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn double_round_trips() {
+        let value = Value::Double(1.5);
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn int_round_trips() {
+        let value = Value::Int(42);
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn pauli_round_trips() {
+        let value = Value::Pauli(Pauli::X);
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn range_round_trips() {
+        let value = Value::Range(Some(0), Some(1), Some(5));
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn result_round_trips() {
+        let value = Value::Result(true);
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn string_round_trips() {
+        let value = Value::String(Rc::from("hello"));
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    #[test]
+    fn tuple_round_trips() {
+        let value = Value::Tuple(Rc::new(vec![Value::Int(1), Value::Bool(true)]));
+        assert_eq!(round_trip(&value).to_string(), value.to_string());
+    }
+
+    fn udt_value() -> Value {
+        Value::new_udt(
+            DefId { package: 0, node: 0 },
+            Rc::from("Point"),
+            vec![
+                (Rc::from("x"), Value::Int(1)),
+                (Rc::from("y"), Value::Int(2)),
+            ],
+        )
+    }
+
+    #[test]
+    fn udt_fails_to_deserialize() {
+        let json = serde_json::to_string(&udt_value()).expect("udt should serialize");
+        let result: Result<Value, _> = serde_json::from_str(&json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn udt_displays_type_name_and_fields() {
+        assert_eq!(udt_value().to_string(), "Point(x = 1, y = 2)");
+    }
+
+    #[test]
+    fn udt_type_name_is_concrete_type() {
+        assert_eq!(udt_value().type_name(), "Point");
+    }
+
+    #[test]
+    fn udt_field_looks_up_by_name() {
+        let value = udt_value();
+        assert_eq!(value.udt_field("x"), Some(&Value::Int(1)));
+        assert_eq!(value.udt_field("z"), None);
+    }
+
+    #[test]
+    fn udt_round_trips_through_try_into_udt() {
+        let (id, name, fields) = udt_value()
+            .try_into_udt()
+            .unwrap_or_else(|_| panic!("value should be a Udt"));
+        assert_eq!(id, DefId { package: 0, node: 0 });
+        assert_eq!(&*name, "Point");
+        assert_eq!(fields.len(), 2);
+    }
+
+    #[test]
+    fn qubit_fails_to_deserialize() {
+        let json = serde_json::to_string(&Value::Qubit(std::ptr::null_mut()))
+            .expect("qubit placeholder should serialize");
+        let result: Result<Value, _> = serde_json::from_str(&json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn closure_fails_to_deserialize() {
+        let id = DefId { package: 0, node: 0 };
+        let json = serde_json::to_string(&Value::Closure(id, HashMap::new()))
+            .expect("closure placeholder should serialize");
+        let result: Result<Value, _> = serde_json::from_str(&json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn global_fails_to_deserialize() {
+        let id = DefId { package: 0, node: 0 };
+        let json =
+            serde_json::to_string(&Value::Global(id)).expect("global placeholder should serialize");
+        let result: Result<Value, _> = serde_json::from_str(&json);
+        assert!(result.is_err());
+    }
+
+    fn display(result: Result<Value, ArithmeticError>) -> String {
+        match result {
+            Ok(v) => v.to_string(),
+            Err(ArithmeticError::Conversion(_)) => "conversion error".to_string(),
+            Err(ArithmeticError::DivideByZero) => "divide by zero".to_string(),
+        }
+    }
+
+    #[test]
+    fn add_matrix() {
+        assert_eq!(display(Value::Int(1).arithmetic_add(Value::Int(2))), "3");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(1)).arithmetic_add(Value::BigInt(BigInt::from(2)))),
+            "3"
+        );
+        assert_eq!(display(Value::Double(1.0).arithmetic_add(Value::Double(2.0))), "3.0");
+        assert_eq!(
+            display(Value::String(Rc::from("a")).arithmetic_add(Value::String(Rc::from("b")))),
+            "ab"
+        );
+        assert_eq!(
+            display(
+                Value::Array(Rc::new(vec![Value::Int(1)]))
+                    .arithmetic_add(Value::Array(Rc::new(vec![Value::Int(2)])))
+            ),
+            "[1, 2]"
+        );
+        assert_eq!(display(Value::Int(1).arithmetic_add(Value::Bool(true))), "conversion error");
+    }
+
+    #[test]
+    fn sub_matrix() {
+        assert_eq!(display(Value::Int(5).arithmetic_subtract(Value::Int(2))), "3");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(5)).arithmetic_subtract(Value::BigInt(BigInt::from(2)))),
+            "3"
+        );
+        assert_eq!(display(Value::Double(5.0).arithmetic_subtract(Value::Double(2.0))), "3.0");
+        assert_eq!(display(Value::Int(5).arithmetic_subtract(Value::Bool(true))), "conversion error");
+    }
+
+    #[test]
+    fn mul_matrix() {
+        assert_eq!(display(Value::Int(2).arithmetic_multiply(Value::Int(3))), "6");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(2)).arithmetic_multiply(Value::BigInt(BigInt::from(3)))),
+            "6"
+        );
+        assert_eq!(display(Value::Double(2.0).arithmetic_multiply(Value::Double(3.0))), "6.0");
+        assert_eq!(display(Value::Int(2).arithmetic_multiply(Value::Bool(true))), "conversion error");
+    }
+
+    #[test]
+    fn div_matrix() {
+        assert_eq!(display(Value::Int(6).arithmetic_divide(Value::Int(3))), "2");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(6)).arithmetic_divide(Value::BigInt(BigInt::from(3)))),
+            "2"
+        );
+        assert_eq!(display(Value::Double(6.0).arithmetic_divide(Value::Double(3.0))), "2.0");
+        assert_eq!(display(Value::Int(1).arithmetic_divide(Value::Int(0))), "divide by zero");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(1)).arithmetic_divide(Value::BigInt(BigInt::from(0)))),
+            "divide by zero"
+        );
+    }
+
+    #[test]
+    fn modulo_matrix() {
+        assert_eq!(display(Value::Int(7).arithmetic_modulo(Value::Int(3))), "1");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(7)).arithmetic_modulo(Value::BigInt(BigInt::from(3)))),
+            "1"
+        );
+        assert_eq!(display(Value::Int(1).arithmetic_modulo(Value::Int(0))), "divide by zero");
+    }
+
+    #[test]
+    fn exp_matrix() {
+        assert_eq!(display(Value::Int(2).arithmetic_exponent(Value::Int(3))), "8");
+        assert_eq!(
+            display(Value::BigInt(BigInt::from(2)).arithmetic_exponent(Value::Int(3))),
+            "8"
+        );
+        assert_eq!(display(Value::Double(2.0).arithmetic_exponent(Value::Double(3.0))), "8.0");
+        assert_eq!(display(Value::Int(2).arithmetic_exponent(Value::Int(-1))), "conversion error");
+    }
+
+    #[test]
+    fn bitwise_matrix() {
+        assert_eq!(display(Value::Int(0b110).bitwise_and(Value::Int(0b011))), "2");
+        assert_eq!(display(Value::Int(0b110).bitwise_or(Value::Int(0b011))), "7");
+        assert_eq!(display(Value::Int(0b110).bitwise_xor(Value::Int(0b011))), "5");
+        assert_eq!(display(Value::Int(1).shift_left(Value::Int(3))), "8");
+        assert_eq!(display(Value::Int(8).shift_right(Value::Int(3))), "1");
+        assert_eq!(display(Value::Int(1).shift_left(Value::Int(-1))), "conversion error");
+    }
+
+    #[test]
+    fn logical_matrix() {
+        assert_eq!(display(Value::Bool(true).logical_and(Value::Bool(false))), "false");
+        assert_eq!(display(Value::Bool(true).logical_or(Value::Bool(false))), "true");
+        assert_eq!(display(Value::Bool(true).logical_and(Value::Int(1))), "conversion error");
+    }
+
+    #[test]
+    fn equal_values_compare_equal() {
+        assert_eq!(Value::Int(1), Value::Int(1));
+        assert_eq!(Value::BigInt(BigInt::from(1)), Value::BigInt(BigInt::from(1)));
+        assert_eq!(
+            Value::Array(Rc::new(vec![Value::Int(1)])),
+            Value::Array(Rc::new(vec![Value::Int(1)]))
+        );
+        assert_eq!(Value::String(Rc::from("a")), Value::String(Rc::from("a")));
+        assert_eq!(Value::Double(f64::NAN), Value::Double(f64::NAN));
+    }
+
+    #[test]
+    fn mismatched_variants_are_unequal() {
+        assert_ne!(Value::Int(1), Value::BigInt(BigInt::from(1)));
+        assert_ne!(Value::Bool(true), Value::Result(true));
+    }
+
+    #[test]
+    fn identity_values_compare_by_identity() {
+        let qubit = std::ptr::null_mut();
+        assert_eq!(Value::Qubit(qubit), Value::Qubit(qubit));
+        assert_ne!(Value::Qubit(qubit), Value::Qubit(std::ptr::dangling_mut::<c_void>()));
+    }
+
+    #[test]
+    fn ordering_within_a_variant_is_defined() {
+        assert!(Value::Int(1) < Value::Int(2));
+        assert!(Value::String(Rc::from("a")) < Value::String(Rc::from("b")));
+        assert_eq!(
+            Value::Int(1).partial_cmp(&Value::Int(2)),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn ordering_across_variants_is_not_comparable() {
+        let (int, boolean) = (Value::Int(1), Value::Bool(true));
+        assert_eq!(int.partial_cmp(&boolean), None);
+        assert_ne!(int.total_cmp(&boolean), Ordering::Equal);
+    }
+
+    #[test]
+    fn partial_cmp_is_none_for_values_with_no_natural_order() {
+        let values = [
+            Value::Int(1),
+            Value::Bool(true),
+            Value::Qubit(std::ptr::null_mut()),
+            Value::Closure(DefId { package: 0, node: 0 }, HashMap::new()),
+            Value::Global(DefId { package: 0, node: 0 }),
+        ];
+        for a in &values {
+            for b in &values {
+                if std::mem::discriminant(a) == std::mem::discriminant(b)
+                    && !matches!(a, Value::Qubit(_) | Value::Closure(_, _) | Value::Global(_))
+                {
+                    assert_eq!(a.partial_cmp(b), Some(Ordering::Equal));
+                } else {
+                    assert_eq!(a.partial_cmp(b), None);
+                }
+                // `total_cmp` is always defined, even where `partial_cmp` is not,
+                // and agrees with it whenever `partial_cmp` does return `Some`.
+                if let Some(ordering) = a.partial_cmp(b) {
+                    assert_eq!(a.total_cmp(b), ordering);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn udt_ordering_of_different_types_is_not_comparable() {
+        let a = Value::new_udt(
+            DefId { package: 0, node: 0 },
+            Rc::from("Point"),
+            vec![(Rc::from("x"), Value::Int(1))],
+        );
+        let b = Value::new_udt(
+            DefId { package: 1, node: 0 },
+            Rc::from("Point"),
+            vec![(Rc::from("x"), Value::Int(1))],
+        );
+        assert_eq!(a.partial_cmp(&b), None);
+        assert_eq!(a.total_cmp(&b), Ordering::Less);
+    }
+
+    #[test]
+    fn udt_total_cmp_compares_def_id_numerically_not_lexicographically() {
+        let a = Value::new_udt(DefId { package: 2, node: 0 }, Rc::from("P"), vec![]);
+        let b = Value::new_udt(DefId { package: 10, node: 0 }, Rc::from("P"), vec![]);
+        assert_eq!(a.total_cmp(&b), Ordering::Less);
+    }
 }
\ No newline at end of file